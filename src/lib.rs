@@ -44,6 +44,35 @@
 //! ```
 //! use collect_slice::CollectSlice;
 //! ```
+//!
+//! # Minimum Supported Rust Version
+//!
+//! This crate requires Rust 1.60 or later: `collect_array`/`collect_array_exhaust` use
+//! const generics (stable since 1.51), and `Cargo.toml` declares the optional `rayon`
+//! dependency with the `dep:` feature syntax (stable since 1.60).
+//!
+//! # Parallel Collection
+//!
+//! Enabling the `rayon` feature adds `ParCollectSlice`, which provides
+//! `par_collect_slice` for `rayon`'s `IndexedParallelIterator`s:
+//!
+//! ```toml
+//! [dependencies]
+//! collect_slice = { version = "^1.2.0", features = ["rayon"] }
+//! ```
+
+#[cfg(feature = "rayon")]
+extern crate rayon;
+
+use std::mem::MaybeUninit;
+use std::ops::Deref;
+use std::ptr;
+
+#[cfg(feature = "rayon")]
+mod par;
+
+#[cfg(feature = "rayon")]
+pub use par::ParCollectSlice;
 
 /// An iterator that can collect into a slice.
 pub trait CollectSlice: Iterator {
@@ -87,6 +116,9 @@ pub trait CollectSlice: Iterator {
     /// If this function succeeds, the number of items written equals the size of the
     /// given slice.
     ///
+    /// If `Iterator::size_hint`'s upper bound already rules out filling the slice, this
+    /// panics immediately instead of writing anything.
+    ///
     /// # Examples
     ///
     /// ```rust,should_panic
@@ -102,6 +134,12 @@ pub trait CollectSlice: Iterator {
     /// (0..5).collect_slice_fill(&mut buf[..]);
     /// ```
     fn collect_slice_fill(&mut self, slice: &mut [Self::Item]) {
+        if let (_, Some(upper)) = self.size_hint() {
+            assert!(upper >= slice.len(),
+                "iterator can yield at most {} items, too few to fill slice of length {}",
+                upper, slice.len());
+        }
+
         assert_eq!(self.collect_slice(slice), slice.len());
     }
 
@@ -110,6 +148,9 @@ pub trait CollectSlice: Iterator {
     ///
     /// Return the number of items written.
     ///
+    /// If `Iterator::size_hint`'s lower bound already rules out the iterator fitting,
+    /// this panics immediately instead of writing anything.
+    ///
     /// # Examples
     ///
     /// ```rust,should_panic
@@ -128,6 +169,11 @@ pub trait CollectSlice: Iterator {
     ///
     /// ```
     fn collect_slice_exhaust(&mut self, slice: &mut [Self::Item]) -> usize {
+        let (lower, _) = self.size_hint();
+        assert!(lower <= slice.len(),
+            "iterator has at least {} items, too many for slice of length {}",
+            lower, slice.len());
+
         let count = self.collect_slice(slice);
         assert!(self.next().is_none());
         count
@@ -166,6 +212,117 @@ pub trait CollectSlice: Iterator {
     fn collect_slice_checked(&mut self, slice: &mut [Self::Item]) {
         assert_eq!(self.collect_slice_exhaust(slice), slice.len());
     }
+
+    /// Loop through the iterator, writing items into the given uninitialized slice until
+    /// either the iterator runs out of items or the slice fills up.
+    ///
+    /// Unlike `collect_slice`, the destination slice doesn't need to already hold valid
+    /// `Self::Item`s, so this works for filling buffers like `[MaybeUninit::uninit(); N]`
+    /// without first default- or zero-initializing them.
+    ///
+    /// Return the initialized prefix of the slice (the items that were just written)
+    /// together with the still-uninitialized remainder. The remainder's contents are
+    /// never dropped. The written items aren't dropped either, since they live in
+    /// `MaybeUninit` storage, so the caller is responsible for disposing of them if
+    /// `Self::Item` doesn't implement `Copy`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::mem::MaybeUninit;
+    /// use collect_slice::CollectSlice;
+    ///
+    /// let mut buf = [MaybeUninit::uninit(); 5];
+    ///
+    /// let (init, rest) = (0..3).collect_slice_uninit(&mut buf[..]);
+    /// assert_eq!(init, [0, 1, 2]);
+    /// assert_eq!(rest.len(), 2);
+    /// ```
+    fn collect_slice_uninit<'a>(&mut self, slice: &'a mut [MaybeUninit<Self::Item>])
+        -> (&'a mut [Self::Item], &'a mut [MaybeUninit<Self::Item>])
+    {
+        let count = slice.iter_mut().zip(self).fold(0, |count, (dest, item)| {
+            unsafe {
+                ptr::write(dest.as_mut_ptr(), item);
+            }
+            count + 1
+        });
+
+        let (init, rest) = slice.split_at_mut(count);
+
+        // Every slot in `init` was just written above, so it's safe to view it as
+        // initialized `Self::Item`s.
+        let init = unsafe {
+            &mut *(init as *mut [MaybeUninit<Self::Item>] as *mut [Self::Item])
+        };
+
+        (init, rest)
+    }
+
+    /// Pull exactly `N` items from the iterator and collect them into a fixed-size
+    /// array, without needing a mutable scratch slice.
+    ///
+    /// Return `None`, dropping any items already written, if the iterator yields fewer
+    /// than `N` items.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use collect_slice::CollectSlice;
+    ///
+    /// let arr: Option<[u8; 4]> = (0..4).collect_array();
+    /// assert_eq!(arr, Some([0, 1, 2, 3]));
+    ///
+    /// let arr: Option<[u8; 4]> = (0..2).collect_array();
+    /// assert_eq!(arr, None);
+    /// ```
+    fn collect_array<const N: usize>(&mut self) -> Option<[Self::Item; N]> {
+        let mut arr: [MaybeUninit<Self::Item>; N] = unsafe {
+            MaybeUninit::uninit().assume_init()
+        };
+
+        let mut count = 0;
+
+        for slot in arr.iter_mut() {
+            match self.next() {
+                Some(item) => {
+                    unsafe {
+                        ptr::write(slot.as_mut_ptr(), item);
+                    }
+                    count += 1;
+                },
+                None => break,
+            }
+        }
+
+        if count == N {
+            Some(unsafe {
+                (&arr as *const [MaybeUninit<Self::Item>; N] as *const [Self::Item; N]).read()
+            })
+        } else {
+            for slot in &mut arr[..count] {
+                unsafe {
+                    ptr::drop_in_place(slot.as_mut_ptr());
+                }
+            }
+            None
+        }
+    }
+
+    /// Perform `collect_array()` and panic if the iterator yielded too few items to
+    /// fill the array.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use collect_slice::CollectSlice;
+    ///
+    /// let arr: [u8; 4] = (0..4).collect_array_exhaust();
+    /// assert_eq!(arr, [0, 1, 2, 3]);
+    /// ```
+    fn collect_array_exhaust<const N: usize>(&mut self) -> [Self::Item; N] {
+        self.collect_array().expect("collect_array_exhaust: too few items to fill array")
+    }
 }
 
 impl<I: ?Sized> CollectSlice for I where I: Iterator {
@@ -177,6 +334,73 @@ impl<I: ?Sized> CollectSlice for I where I: Iterator {
     }
 }
 
+/// A cursor over a mutable slice that fills it incrementally, appending the items
+/// collected from each iterator after the ones collected by previous calls.
+///
+/// This lets multiple iterators be merged into a single stack array without manually
+/// sub-slicing and tracking write offsets between calls.
+///
+/// # Examples
+///
+/// ```
+/// use collect_slice::SliceCursor;
+///
+/// let mut buf = [0; 10];
+/// let mut cursor = SliceCursor::new(&mut buf[..]);
+///
+/// assert_eq!(cursor.collect_from(0..4), 4);
+/// assert_eq!(cursor.collect_from(10..13), 3);
+///
+/// assert_eq!(cursor.written(), 7);
+/// assert_eq!(cursor.remaining(), 3);
+/// assert_eq!(&cursor[..], &[0, 1, 2, 3, 10, 11, 12]);
+/// ```
+pub struct SliceCursor<'a, T: 'a> {
+    slice: &'a mut [T],
+    len: usize,
+}
+
+impl<'a, T: 'a> SliceCursor<'a, T> {
+    /// Create a cursor over the given slice, with an empty write position at the
+    /// front.
+    pub fn new(slice: &'a mut [T]) -> Self {
+        SliceCursor {
+            slice,
+            len: 0,
+        }
+    }
+
+    /// Collect the given iterator into the unwritten remainder of the slice, starting
+    /// right after the items written by any previous calls.
+    ///
+    /// Return the number of items written and advance the write position by that
+    /// amount, same truncating behavior as `CollectSlice::collect_slice`.
+    pub fn collect_from<I: Iterator<Item = T>>(&mut self, iter: I) -> usize {
+        let mut iter = iter;
+        let count = iter.collect_slice(&mut self.slice[self.len..]);
+        self.len += count;
+        count
+    }
+
+    /// Return the number of items written into the slice so far.
+    pub fn written(&self) -> usize {
+        self.len
+    }
+
+    /// Return the number of slots still unwritten.
+    pub fn remaining(&self) -> usize {
+        self.slice.len() - self.len
+    }
+}
+
+impl<'a, T: 'a> Deref for SliceCursor<'a, T> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        &self.slice[..self.len]
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -307,6 +531,229 @@ mod test {
         }).collect_slice_fill(&mut buf[..]);
     }
 
+    #[test]
+    fn test_uninit_basic() {
+        let mut buf = [MaybeUninit::uninit(); 5];
+
+        let (init, rest) = (0..5).map(|i| i + 1).collect_slice_uninit(&mut buf[..]);
+
+        assert_eq!(init, [1, 2, 3, 4, 5]);
+        assert_eq!(rest.len(), 0);
+    }
+
+    #[test]
+    fn test_uninit_under() {
+        let mut buf = [MaybeUninit::uninit(); 5];
+
+        let (init, rest) = (0..3).map(|i| i + 1).collect_slice_uninit(&mut buf[..]);
+
+        assert_eq!(init, [1, 2, 3]);
+        assert_eq!(rest.len(), 2);
+    }
+
+    #[test]
+    fn test_uninit_over() {
+        let mut buf = [MaybeUninit::uninit(); 3];
+
+        let mut iter = (0..5).map(|i| i + 1);
+
+        let (init, rest) = iter.collect_slice_uninit(&mut buf[..]);
+
+        assert_eq!(init, [1, 2, 3]);
+        assert_eq!(rest.len(), 0);
+
+        assert_eq!(iter.next().unwrap(), 4);
+        assert_eq!(iter.next().unwrap(), 5);
+    }
+
+    #[test]
+    fn test_uninit_empty_iter() {
+        let mut buf = [MaybeUninit::uninit(); 5];
+
+        let (init, rest) = (0..0).collect_slice_uninit(&mut buf[..]);
+
+        assert_eq!(init.len(), 0);
+        assert_eq!(rest.len(), 5);
+    }
+
+    #[test]
+    fn test_array_basic() {
+        let arr: Option<[u8; 4]> = (0..4).collect_array();
+        assert_eq!(arr, Some([0, 1, 2, 3]));
+    }
+
+    #[test]
+    fn test_array_under() {
+        let arr: Option<[u8; 4]> = (0..2).collect_array();
+        assert_eq!(arr, None);
+    }
+
+    #[test]
+    fn test_array_over() {
+        let mut iter = 0..10u8;
+        let arr: Option<[u8; 4]> = iter.by_ref().collect_array();
+
+        assert_eq!(arr, Some([0, 1, 2, 3]));
+        assert_eq!(iter.next().unwrap(), 4);
+    }
+
+    #[test]
+    fn test_array_empty() {
+        let arr: Option<[u8; 0]> = (0..10).collect_array();
+        assert_eq!(arr, Some([]));
+    }
+
+    #[test]
+    fn test_array_exhaust() {
+        let arr: [u8; 4] = (0..4).collect_array_exhaust();
+        assert_eq!(arr, [0, 1, 2, 3]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_array_exhaust_under() {
+        let _: [u8; 4] = (0..2).collect_array_exhaust();
+    }
+
+    #[test]
+    fn test_array_drops_partial_on_failure() {
+        use std::cell::Cell;
+        use std::rc::Rc;
+
+        struct DropCounter(Rc<Cell<usize>>);
+
+        impl Drop for DropCounter {
+            fn drop(&mut self) {
+                self.0.set(self.0.get() + 1);
+            }
+        }
+
+        let count = Rc::new(Cell::new(0));
+
+        let arr: Option<[DropCounter; 4]> =
+            (0..2).map(|_| DropCounter(count.clone())).collect_array();
+
+        assert!(arr.is_none());
+        assert_eq!(count.get(), 2);
+    }
+
+    #[test]
+    fn test_cursor_basic() {
+        let mut buf = [0; 10];
+        let mut cursor = SliceCursor::new(&mut buf[..]);
+
+        assert_eq!(cursor.collect_from(0..4), 4);
+        assert_eq!(cursor.collect_from(10..13), 3);
+
+        assert_eq!(cursor.written(), 7);
+        assert_eq!(cursor.remaining(), 3);
+        assert_eq!(&cursor[..], &[0, 1, 2, 3, 10, 11, 12]);
+    }
+
+    #[test]
+    fn test_cursor_under() {
+        let mut buf = [0; 10];
+        let mut cursor = SliceCursor::new(&mut buf[..]);
+
+        assert_eq!(cursor.collect_from(0..3), 3);
+
+        assert_eq!(cursor.written(), 3);
+        assert_eq!(cursor.remaining(), 7);
+    }
+
+    #[test]
+    fn test_cursor_over() {
+        let mut buf = [0; 3];
+        let mut cursor = SliceCursor::new(&mut buf[..]);
+
+        assert_eq!(cursor.collect_from(0..10), 3);
+
+        assert_eq!(cursor.written(), 3);
+        assert_eq!(cursor.remaining(), 0);
+        assert_eq!(&cursor[..], &[0, 1, 2]);
+
+        assert_eq!(cursor.collect_from(0..1), 0);
+    }
+
+    #[test]
+    fn test_cursor_empty_slice() {
+        let mut buf: [i32; 0] = [];
+        let mut cursor = SliceCursor::new(&mut buf[..]);
+
+        assert_eq!(cursor.collect_from(0..10), 0);
+        assert_eq!(cursor.written(), 0);
+        assert_eq!(cursor.remaining(), 0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_fill_size_hint_fails_fast() {
+        let mut buf = [9; 5];
+
+        (0..3).map(|i| i + 1).collect_slice_fill(&mut buf[..]);
+    }
+
+    #[test]
+    fn test_fill_size_hint_leaves_buffer_untouched() {
+        let mut buf = [9; 5];
+
+        let result = ::std::panic::catch_unwind(::std::panic::AssertUnwindSafe(|| {
+            (0..3).map(|i| i + 1).collect_slice_fill(&mut buf[..]);
+        }));
+
+        assert!(result.is_err());
+        assert_eq!(buf, [9; 5]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_exhaust_size_hint_fails_fast() {
+        let mut buf = [0; 5];
+
+        (0..10).map(|i| i + 1).collect_slice_exhaust(&mut buf[..]);
+    }
+
+    #[test]
+    fn test_exhaust_size_hint_leaves_buffer_untouched() {
+        let mut buf = [9; 5];
+
+        let result = ::std::panic::catch_unwind(::std::panic::AssertUnwindSafe(|| {
+            (0..10).map(|i| i + 1).collect_slice_exhaust(&mut buf[..]);
+        }));
+
+        assert!(result.is_err());
+        assert_eq!(buf, [9; 5]);
+    }
+
+    /// An iterator that doesn't override `size_hint`, so it's stuck with the default
+    /// `(0, None)` and can't be fast-failed on.
+    struct Unknown<I>(I);
+
+    impl<I: Iterator> Iterator for Unknown<I> {
+        type Item = I::Item;
+
+        fn next(&mut self) -> Option<I::Item> {
+            self.0.next()
+        }
+    }
+
+    #[test]
+    fn test_fill_inconclusive_hint_falls_back_to_post_hoc() {
+        let mut buf = [0; 5];
+
+        Unknown((0..5).map(|i| i + 1)).collect_slice_fill(&mut buf[..]);
+
+        assert_eq!(buf, [1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_fill_inconclusive_hint_still_panics_post_hoc() {
+        let mut buf = [0; 5];
+
+        Unknown((0..3).map(|i| i + 1)).collect_slice_fill(&mut buf[..]);
+    }
+
     #[test]
     fn test_unsized() {
         let mut buf = [0; 5];
@@ -0,0 +1,148 @@
+//! Parallel slice collection, built on `rayon`'s `IndexedParallelIterator`.
+
+use rayon::iter::plumbing::{Consumer, Folder, Reducer, UnindexedConsumer};
+use rayon::iter::IndexedParallelIterator;
+
+/// An indexed parallel iterator that can collect into a slice.
+pub trait ParCollectSlice: IndexedParallelIterator {
+    /// Split the destination slice into contiguous chunks and, in parallel, write each
+    /// chunk from a matching sub-iterator so item `i` always lands at index `i`, same
+    /// ordering as the sequential `CollectSlice::collect_slice`.
+    ///
+    /// Return `min(self.len(), slice.len())`, the number of items actually written.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate rayon;
+    /// extern crate collect_slice;
+    ///
+    /// use rayon::prelude::*;
+    /// use collect_slice::ParCollectSlice;
+    ///
+    /// let mut buf = [0; 5];
+    /// let count = (0..5).into_par_iter().map(|i| i * 2).par_collect_slice(&mut buf[..]);
+    ///
+    /// assert_eq!(count, 5);
+    /// assert_eq!(buf, [0, 2, 4, 6, 8]);
+    /// ```
+    fn par_collect_slice(self, slice: &mut [Self::Item]) -> usize {
+        let len = ::std::cmp::min(self.len(), slice.len());
+
+        // `take` keeps the driven iterator's reported length in sync with the
+        // slice's, so the producer and `SliceConsumer` agree on every split point
+        // rayon picks while recursing.
+        self.take(len).drive(SliceConsumer { slice: &mut slice[..len] })
+    }
+}
+
+impl<I: IndexedParallelIterator> ParCollectSlice for I {}
+
+struct SliceConsumer<'a, T: 'a> {
+    slice: &'a mut [T],
+}
+
+impl<'a, T: Send> Consumer<T> for SliceConsumer<'a, T> {
+    type Folder = SliceFolder<'a, T>;
+    type Reducer = SliceReducer;
+    type Result = usize;
+
+    fn split_at(self, index: usize) -> (Self, Self, Self::Reducer) {
+        let (left, right) = self.slice.split_at_mut(index);
+        (SliceConsumer { slice: left }, SliceConsumer { slice: right }, SliceReducer)
+    }
+
+    fn into_folder(self) -> Self::Folder {
+        SliceFolder { slice: self.slice, written: 0 }
+    }
+
+    fn full(&self) -> bool {
+        self.slice.is_empty()
+    }
+}
+
+impl<'a, T: Send> UnindexedConsumer<T> for SliceConsumer<'a, T> {
+    fn split_off_left(&self) -> Self {
+        unreachable!("par_collect_slice only ever drives an indexed producer")
+    }
+
+    fn to_reducer(&self) -> Self::Reducer {
+        SliceReducer
+    }
+}
+
+struct SliceFolder<'a, T: 'a> {
+    slice: &'a mut [T],
+    written: usize,
+}
+
+impl<'a, T> Folder<T> for SliceFolder<'a, T> {
+    type Result = usize;
+
+    fn consume(mut self, item: T) -> Self {
+        self.slice[self.written] = item;
+        self.written += 1;
+        self
+    }
+
+    fn complete(self) -> usize {
+        self.written
+    }
+
+    fn full(&self) -> bool {
+        self.written >= self.slice.len()
+    }
+}
+
+struct SliceReducer;
+
+impl Reducer<usize> for SliceReducer {
+    fn reduce(self, left: usize, right: usize) -> usize {
+        left + right
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rayon::prelude::*;
+
+    #[test]
+    fn test_basic() {
+        let mut buf = [0; 5];
+
+        let count = (0..5).into_par_iter().par_collect_slice(&mut buf[..]);
+
+        assert_eq!(count, 5);
+        assert_eq!(buf, [0, 1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn test_under() {
+        let mut buf = [0; 10];
+
+        let count = (0..5).into_par_iter().par_collect_slice(&mut buf[..]);
+
+        assert_eq!(count, 5);
+        assert_eq!(buf, [0, 1, 2, 3, 4, 0, 0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn test_over() {
+        let mut buf = [0; 5];
+
+        let count = (0..1000).into_par_iter().par_collect_slice(&mut buf[..]);
+
+        assert_eq!(count, 5);
+        assert_eq!(buf, [0, 1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn test_empty_slice() {
+        let mut buf: [i32; 0] = [];
+
+        let count = (0..1000).into_par_iter().par_collect_slice(&mut buf[..]);
+
+        assert_eq!(count, 0);
+    }
+}